@@ -1,12 +1,17 @@
 use std::process::exit;
 
 use clap::{arg, Command};
-use droll::{base_prng_engine, interpreter::eval, parser::parse};
+use droll::{
+    base_prng_engine,
+    interpreter::{eval, eval_breakdown},
+    parser::parse,
+};
 
 fn main() {
     let cmd = Command::new("droll")
         .about("Parse dice notation and print the result")
         .arg(arg!(<DICE_NOTATION>))
+        .arg(arg!(-b --breakdown "Print the individual dice that produced the result"))
         .arg_required_else_help(true);
 
     let matches = cmd.get_matches();
@@ -15,13 +20,21 @@ fn main() {
         .get_one::<String>("DICE_NOTATION")
         .expect("Required input argument is missing");
 
-    match parse(input) {
-        Ok(parse_tree) => {
-            println!("{}", eval(base_prng_engine)(parse_tree));
+    let result = parse(input).and_then(|parse_tree| {
+        if matches.get_flag("breakdown") {
+            eval_breakdown(base_prng_engine)(parse_tree).map(|r| r.to_string())
+        } else {
+            eval(base_prng_engine)(parse_tree).map(|r| r.to_string())
+        }
+    });
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
             exit(0);
         }
         Err(e) => {
-            println!("{}", e);
+            println!("{}", e.report(input));
             exit(1);
         }
     };
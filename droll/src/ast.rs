@@ -9,6 +9,27 @@ pub enum Operator {
     Plus,
     /// Represents the minus operator.
     Minus,
+    /// Represents the multiply operator.
+    Multiply,
+    /// Represents the divide operator.
+    Divide,
+    /// Represents the power operator.
+    Power,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Represents a postfix dice modifier in the parse tree.
+pub enum Modifier {
+    /// Keep the highest `n` dice of a roll.
+    Keep(usize),
+    /// Keep the lowest `n` dice of a roll.
+    KeepLow(usize),
+    /// Drop the highest `n` dice of a roll.
+    DropHigh(usize),
+    /// Drop the lowest `n` dice of a roll.
+    DropLow(usize),
+    /// Re-roll and add whenever a die shows its maximum face.
+    Explode,
 }
 
 impl Display for Operator {
@@ -17,6 +38,21 @@ impl Display for Operator {
             Operator::Die => write!(f, "d"),
             Operator::Plus => write!(f, "+"),
             Operator::Minus => write!(f, "-"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Divide => write!(f, "/"),
+            Operator::Power => write!(f, "^"),
+        }
+    }
+}
+
+impl Display for Modifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self {
+            Modifier::Keep(n) => write!(f, "k{}", n),
+            Modifier::KeepLow(n) => write!(f, "kl{}", n),
+            Modifier::DropHigh(n) => write!(f, "dh{}", n),
+            Modifier::DropLow(n) => write!(f, "dl{}", n),
+            Modifier::Explode => write!(f, "!"),
         }
     }
 }
@@ -28,6 +64,12 @@ pub enum Expr {
     Binary(Box<Expr>, Box<Expr>, Operator),
     /// Represents the right-associative unary expression in the parse tree.
     Unary(Box<Expr>, Operator),
+    /// Represents the left-associative postfix expression in the parse tree.
+    Postfix(Box<Expr>, Modifier),
+    /// Represents a Fudge dice roll of the given amount in the parse tree.
+    FudgeRoll(Box<Expr>),
+    /// Represents a percentile dice roll of the given amount in the parse tree.
+    PercentileRoll(Box<Expr>),
     /// Represents the numeric literal in the parse tree.
     NumericLiteral(usize),
 }
@@ -46,6 +88,21 @@ impl Display for Expr {
                 write!(f, " {} {}", lhs, rhs)?;
                 write!(f, ")")
             }
+            Expr::Postfix(lhs, modifier) => {
+                write!(f, "({}", modifier)?;
+                write!(f, " {}", lhs)?;
+                write!(f, ")")
+            }
+            Expr::FudgeRoll(amount) => {
+                write!(f, "(dF")?;
+                write!(f, " {}", amount)?;
+                write!(f, ")")
+            }
+            Expr::PercentileRoll(amount) => {
+                write!(f, "(d%")?;
+                write!(f, " {}", amount)?;
+                write!(f, ")")
+            }
         }
     }
 }
@@ -60,6 +117,21 @@ pub fn unary_expr(rhs: Expr, op: Operator) -> Expr {
     Expr::Unary(Box::new(rhs), op)
 }
 
+/// Helper function to create postfix expression.
+pub fn postfix_expr(lhs: Expr, modifier: Modifier) -> Expr {
+    Expr::Postfix(Box::new(lhs), modifier)
+}
+
+/// Helper function to create a Fudge dice roll expression.
+pub fn fudge_roll(amount: Expr) -> Expr {
+    Expr::FudgeRoll(Box::new(amount))
+}
+
+/// Helper function to create a percentile dice roll expression.
+pub fn percentile_roll(amount: Expr) -> Expr {
+    Expr::PercentileRoll(Box::new(amount))
+}
+
 /// Helper function to create binary expression.
 pub fn binary_expr(lhs: Expr, rhs: Expr, op: Operator) -> Expr {
     Expr::Binary(Box::new(lhs), Box::new(rhs), op)
@@ -0,0 +1,41 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// Represents a recoverable lexing or parsing error, carrying a human readable message and the byte
+/// offset into the input at which the offending token or character begins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    message: String,
+    offset: usize,
+}
+
+impl Error {
+    /// Creates a new error from a message and the offset at which it occurred.
+    pub fn new(message: impl Into<String>, offset: usize) -> Self {
+        Error {
+            message: message.into(),
+            offset,
+        }
+    }
+
+    /// Returns the byte offset into the input at which the error occurred.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the error message without the positional suffix.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Renders the error against the original `input`, placing a caret underneath the offending
+    /// column.
+    pub fn report(&self, input: &str) -> String {
+        format!("{}\n{}^\n{}", input, " ".repeat(self.offset), self)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} at column {}", self.message, self.offset + 1)
+    }
+}
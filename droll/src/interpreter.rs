@@ -1,6 +1,49 @@
+use std::fmt::{Display, Formatter};
 use std::ops::Neg;
 
-use crate::ast::{Expr, Operator};
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Expr, Modifier, Operator};
+use crate::error::Error;
+
+/// Upper bound on re-rolls performed for a single exploding die, preventing infinite recursion when
+/// a die always shows its maximum face (e.g. a `d1`).
+const MAX_EXPLODE_DEPTH: usize = 100;
+
+/// Records a single die that was rolled, capturing the number of faces it has and the face it
+/// landed on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DieRoll {
+    /// The number of faces on the die (e.g. `6` for a `d6`, `100` for `d%`, `3` for `dF`).
+    pub size: isize,
+    /// The face the die landed on.
+    pub face: isize,
+}
+
+/// The outcome of evaluating a parse tree with [`eval_breakdown`], pairing the final total with the
+/// individual dice that produced it so callers can show a roll breakdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollResult {
+    /// The final evaluated total.
+    pub total: isize,
+    /// The dice that contributed to the total, in evaluation order. Dice discarded by a keep or drop
+    /// modifier are left out, and the dice such a modifier keeps are listed in sorted order.
+    pub rolls: Vec<DieRoll>,
+}
+
+impl RollResult {
+    /// Returns the final evaluated total.
+    pub fn total(&self) -> isize {
+        self.total
+    }
+}
+
+impl Display for RollResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let faces: Vec<String> = self.rolls.iter().map(|roll| roll.face.to_string()).collect();
+        write!(f, "[{}] = {}", faces.join(", "), self.total)
+    }
+}
 
 /// Evaluates the passed parse tree ([`Expr`]) recursively. [`eval`] is a high-order function that
 /// takes, as it's first argument, a random number generator engine. Calling the function will
@@ -19,40 +62,306 @@ use crate::ast::{Expr, Operator};
 /// let dice_notation = "1d20+10";
 /// let parse_tree = parse(dice_notation).unwrap();
 /// let prng_engine = || 1f64; // Engine to always roll highest result.
-/// let evaluation = eval(prng_engine)(parse_tree);
+/// let evaluation = eval(prng_engine)(parse_tree).unwrap();
 ///
 /// assert_eq!(30, evaluation);
 /// ```
-pub fn eval(prng: fn() -> f64) -> impl Fn(Expr) -> isize {
-    move |ast: Expr| -> isize {
+///
+/// Applying a modifier to something other than a roll is reported as an [`Error`] rather than
+/// panicking. The parse tree carries no source spans, so errors raised during evaluation have no
+/// column information and report at offset `0`.
+pub fn eval(prng: fn() -> f64) -> impl Fn(Expr) -> Result<isize, Error> {
+    move |ast: Expr| -> Result<isize, Error> {
         let e = eval(prng);
         match ast {
-            Expr::NumericLiteral(n) => n as isize,
+            Expr::NumericLiteral(n) => Ok(n as isize),
+            Expr::Binary(lhs, rhs, op) => match op {
+                Operator::Die => Ok(roll_dice(prng)(e(*lhs)?, e(*rhs)?).iter().sum()),
+                Operator::Plus => Ok(e(*lhs)? + e(*rhs)?),
+                Operator::Minus => Ok(e(*lhs)? - e(*rhs)?),
+                Operator::Multiply => Ok(e(*lhs)? * e(*rhs)?),
+                Operator::Divide => checked_div(e(*lhs)?, e(*rhs)?),
+                Operator::Power => checked_power(e(*lhs)?, e(*rhs)?),
+            },
+            Expr::Unary(rhs, op) => match op {
+                Operator::Die => Ok(roll_dice(prng)(1, e(*rhs)?).iter().sum()),
+                Operator::Plus => e(*rhs),
+                Operator::Minus => Ok(e(*rhs)?.neg()),
+                op => unreachable!("`{}` is not a unary operator", op),
+            },
+            Expr::Postfix(lhs, modifier) => {
+                let (amount, sides) = match *lhs {
+                    Expr::Binary(amount, sides, Operator::Die) => (e(*amount)?, e(*sides)?),
+                    Expr::Unary(sides, Operator::Die) => (1, e(*sides)?),
+                    lhs => return Err(Error::new(format!("`{}` cannot be modified", lhs), 0)),
+                };
+                let faces = calc_modifier(prng)(roll_dice(prng)(amount, sides), sides, modifier);
+                Ok(faces.iter().sum())
+            }
+            Expr::FudgeRoll(amount) => Ok(calc_fudge(prng)(e(*amount)?)),
+            Expr::PercentileRoll(amount) => Ok(calc_percentile(prng)(e(*amount)?)),
+        }
+    }
+}
+
+/// Evaluates the passed parse tree ([`Expr`]) like [`eval`], but returns a [`RollResult`] carrying
+/// both the final total and the individual dice that produced it. Its [`Display`] form lists the
+/// dice faces and the total, e.g. `[4, 1, 6] = 13` for `3d6+2`; numeric literals and operators are
+/// not recorded, so the `+ 2` does not appear in the breakdown.
+///
+/// Like [`eval`], errors raised while evaluating carry no column information and report at offset
+/// `0`.
+///
+/// # Example
+///
+/// Basic Usage:
+///
+/// ```
+/// use droll::parser::{parse};
+/// use droll::interpreter::{eval_breakdown};
+///
+/// let parse_tree = parse("1d20+10").unwrap();
+/// let prng_engine = || 1f64; // Engine to always roll highest result.
+/// let result = eval_breakdown(prng_engine)(parse_tree).unwrap();
+///
+/// assert_eq!(30, result.total());
+/// assert_eq!(1, result.rolls.len());
+/// ```
+pub fn eval_breakdown(prng: fn() -> f64) -> impl Fn(Expr) -> Result<RollResult, Error> {
+    move |ast: Expr| -> Result<RollResult, Error> {
+        let e = eval_breakdown(prng);
+        match ast {
+            Expr::NumericLiteral(n) => Ok(RollResult {
+                total: n as isize,
+                rolls: vec![],
+            }),
             Expr::Binary(lhs, rhs, op) => match op {
-                Operator::Die => calc_roll(prng)(e(*lhs), e(*rhs)),
-                Operator::Plus => e(*lhs) + e(*rhs),
-                Operator::Minus => e(*lhs) - e(*rhs),
+                Operator::Die => Ok(roll_breakdown(prng)(e(*lhs)?, e(*rhs)?)),
+                Operator::Plus => Ok(combine(e(*lhs)?, e(*rhs)?, |a, b| a + b)),
+                Operator::Minus => Ok(combine(e(*lhs)?, e(*rhs)?, |a, b| a - b)),
+                Operator::Multiply => Ok(combine(e(*lhs)?, e(*rhs)?, |a, b| a * b)),
+                Operator::Divide => try_combine(e(*lhs)?, e(*rhs)?, checked_div),
+                Operator::Power => try_combine(e(*lhs)?, e(*rhs)?, checked_power),
             },
             Expr::Unary(rhs, op) => match op {
-                Operator::Die => calc_roll(prng)(1, e(*rhs)),
+                Operator::Die => Ok(roll_breakdown(prng)(
+                    RollResult {
+                        total: 1,
+                        rolls: vec![],
+                    },
+                    e(*rhs)?,
+                )),
                 Operator::Plus => e(*rhs),
-                Operator::Minus => e(*rhs).neg(),
+                Operator::Minus => {
+                    let rhs = e(*rhs)?;
+                    Ok(RollResult {
+                        total: rhs.total.neg(),
+                        rolls: rhs.rolls,
+                    })
+                }
+                op => unreachable!("`{}` is not a unary operator", op),
             },
+            Expr::Postfix(lhs, modifier) => {
+                let (amount, sides) = match *lhs {
+                    Expr::Binary(amount, sides, Operator::Die) => (e(*amount)?, e(*sides)?),
+                    Expr::Unary(sides, Operator::Die) => (
+                        RollResult {
+                            total: 1,
+                            rolls: vec![],
+                        },
+                        e(*sides)?,
+                    ),
+                    lhs => return Err(Error::new(format!("`{}` cannot be modified", lhs), 0)),
+                };
+                let initial = roll_dice(prng)(amount.total, sides.total);
+                let faces = calc_modifier(prng)(initial, sides.total, modifier);
+                let mut rolls = amount.rolls;
+                rolls.extend(sides.rolls);
+                rolls.extend(faces.iter().map(|&face| DieRoll {
+                    size: sides.total,
+                    face,
+                }));
+                Ok(RollResult {
+                    total: faces.iter().sum(),
+                    rolls,
+                })
+            }
+            Expr::FudgeRoll(amount) => Ok(fudge_breakdown(prng)(e(*amount)?)),
+            Expr::PercentileRoll(amount) => Ok(percentile_breakdown(prng)(e(*amount)?)),
         }
     }
 }
 
-fn calc_roll(prng: fn() -> f64) -> impl Fn(isize, isize) -> isize {
-    move |amount: isize, sides: isize| -> isize {
-        (amount as f64 * (prng() * sides as f64).round().max(1.0)) as isize
+/// Raises `base` to `exp`, rejecting a negative exponent and guarding against overflow so a large
+/// power surfaces as an [`Error`] rather than panicking.
+fn checked_power(base: isize, exp: isize) -> Result<isize, Error> {
+    let exp = u32::try_from(exp)
+        .map_err(|_| Error::new("exponent must not be negative", 0))?;
+    base.checked_pow(exp)
+        .ok_or_else(|| Error::new("power overflowed", 0))
+}
+
+/// Divides `lhs` by `rhs`, returning an [`Error`] when the divisor is zero instead of panicking.
+fn checked_div(lhs: isize, rhs: isize) -> Result<isize, Error> {
+    lhs.checked_div(rhs)
+        .ok_or_else(|| Error::new("cannot divide by zero", 0))
+}
+
+/// Combines two [`RollResult`]s with a fallible arithmetic operation, concatenating the dice they
+/// rolled and propagating any [`Error`] the operation raises.
+fn try_combine(
+    lhs: RollResult,
+    rhs: RollResult,
+    op: fn(isize, isize) -> Result<isize, Error>,
+) -> Result<RollResult, Error> {
+    let total = op(lhs.total, rhs.total)?;
+    let mut rolls = lhs.rolls;
+    rolls.extend(rhs.rolls);
+    Ok(RollResult { total, rolls })
+}
+
+/// Combines two [`RollResult`]s with an arithmetic operation, concatenating the dice they rolled.
+fn combine(lhs: RollResult, rhs: RollResult, op: fn(isize, isize) -> isize) -> RollResult {
+    let mut rolls = lhs.rolls;
+    rolls.extend(rhs.rolls);
+    RollResult {
+        total: op(lhs.total, rhs.total),
+        rolls,
+    }
+}
+
+/// Rolls `amount.total` dice of `sides.total` faces, recording each individual die alongside the
+/// dice already rolled while evaluating the amount and sides sub-expressions.
+fn roll_breakdown(prng: fn() -> f64) -> impl Fn(RollResult, RollResult) -> RollResult {
+    move |amount: RollResult, sides: RollResult| -> RollResult {
+        let faces = roll_dice(prng)(amount.total, sides.total);
+        let mut rolls = amount.rolls;
+        rolls.extend(sides.rolls);
+        rolls.extend(faces.iter().map(|&face| DieRoll {
+            size: sides.total,
+            face,
+        }));
+        RollResult {
+            total: faces.iter().sum(),
+            rolls,
+        }
+    }
+}
+
+/// Rolls `amount.total` Fudge dice, recording each as a three-faced die.
+fn fudge_breakdown(prng: fn() -> f64) -> impl Fn(RollResult) -> RollResult {
+    move |amount: RollResult| -> RollResult {
+        let faces: Vec<isize> = (0..amount.total).map(|_| roll_fudge(prng)).collect();
+        let mut rolls = amount.rolls;
+        rolls.extend(faces.iter().map(|&face| DieRoll { size: 3, face }));
+        RollResult {
+            total: faces.iter().sum(),
+            rolls,
+        }
     }
 }
 
+/// Rolls `amount.total` percentile dice, recording each as a hundred-faced die.
+fn percentile_breakdown(prng: fn() -> f64) -> impl Fn(RollResult) -> RollResult {
+    move |amount: RollResult| -> RollResult {
+        let faces: Vec<isize> = (0..amount.total).map(|_| roll_percentile(prng)).collect();
+        let mut rolls = amount.rolls;
+        rolls.extend(faces.iter().map(|&face| DieRoll { size: 100, face }));
+        RollResult {
+            total: faces.iter().sum(),
+            rolls,
+        }
+    }
+}
+
+/// Sums `amount` Fudge dice, each yielding a uniform value in `{-1, 0, +1}`.
+fn calc_fudge(prng: fn() -> f64) -> impl Fn(isize) -> isize {
+    move |amount: isize| -> isize { (0..amount).map(|_| roll_fudge(prng)).sum() }
+}
+
+/// Sums `amount` percentile dice, each yielding a value in `1..=100`.
+fn calc_percentile(prng: fn() -> f64) -> impl Fn(isize) -> isize {
+    move |amount: isize| -> isize { (0..amount).map(|_| roll_percentile(prng)).sum() }
+}
+
+/// Rolls a single Fudge die, yielding a uniform value in `{-1, 0, +1}`.
+fn roll_fudge(prng: fn() -> f64) -> isize {
+    (prng() * 3.0).floor().min(2.0) as isize - 1
+}
+
+/// Rolls a single percentile die, yielding a value in `1..=100`.
+fn roll_percentile(prng: fn() -> f64) -> isize {
+    (prng() * 100.0).round().max(1.0) as isize
+}
+
+/// Rolls `amount` independent dice of the given number of `sides`, returning each individual face.
+fn roll_dice(prng: fn() -> f64) -> impl Fn(isize, isize) -> Vec<isize> {
+    move |amount: isize, sides: isize| -> Vec<isize> {
+        (0..amount).map(|_| roll_die(prng, sides)).collect()
+    }
+}
+
+/// Rolls a single die of the given number of `sides`, yielding a face in `1..=sides`.
+fn roll_die(prng: fn() -> f64, sides: isize) -> isize {
+    (prng() * sides as f64).round().max(1.0) as isize
+}
+
+/// Applies a postfix [`Modifier`] to the individual die results of a roll, returning only the dice
+/// that contribute to the total. Keep and drop modifiers sort and select, while
+/// [`Modifier::Explode`] re-rolls and adds whenever a die shows its maximum face. Returning the
+/// surviving faces (rather than their sum) lets callers sum them for the total and record exactly
+/// the dice that produced it.
+fn calc_modifier(prng: fn() -> f64) -> impl Fn(Vec<isize>, isize, Modifier) -> Vec<isize> {
+    move |mut rolls: Vec<isize>, sides: isize, modifier: Modifier| -> Vec<isize> {
+        match modifier {
+            Modifier::Keep(n) => {
+                rolls.sort_unstable_by(|a, b| b.cmp(a));
+                rolls.truncate(n);
+                rolls
+            }
+            Modifier::KeepLow(n) => {
+                rolls.sort_unstable();
+                rolls.truncate(n);
+                rolls
+            }
+            Modifier::DropHigh(n) => {
+                rolls.sort_unstable_by(|a, b| b.cmp(a));
+                rolls.into_iter().skip(n).collect()
+            }
+            Modifier::DropLow(n) => {
+                rolls.sort_unstable();
+                rolls.into_iter().skip(n).collect()
+            }
+            Modifier::Explode => rolls
+                .iter()
+                .flat_map(|&roll| explode(prng, roll, sides))
+                .collect(),
+        }
+    }
+}
+
+/// Re-rolls a single die as long as it shows its maximum face, returning every face rolled
+/// (including the re-rolls) and capping the recursion at [`MAX_EXPLODE_DEPTH`].
+fn explode(prng: fn() -> f64, roll: isize, sides: isize) -> Vec<isize> {
+    let mut faces = vec![roll];
+    let mut last = roll;
+    let mut depth = 0;
+
+    while last == sides && depth < MAX_EXPLODE_DEPTH {
+        last = roll_die(prng, sides);
+        faces.push(last);
+        depth += 1;
+    }
+
+    faces
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ast::{
-        binary_expr, binary_roll_expr, numeric_literal, unary_expr, unary_roll_expr, Operator,
+        binary_expr, binary_roll_expr, fudge_roll, numeric_literal, percentile_roll, postfix_expr,
+        unary_expr, unary_roll_expr, Modifier, Operator,
     };
     #[test]
     fn test_eval() {
@@ -79,15 +388,76 @@ mod tests {
                 ),
                 -1,
             ),
+            (
+                binary_expr(numeric_literal(2), numeric_literal(3), Operator::Power),
+                8,
+            ),
+            (
+                binary_expr(
+                    numeric_literal(2),
+                    binary_expr(numeric_literal(3), numeric_literal(2), Operator::Power),
+                    Operator::Power,
+                ),
+                512,
+            ),
+            (postfix_expr(binary_roll_expr(4, 6), Modifier::Keep(3)), 18),
+            (postfix_expr(binary_roll_expr(4, 6), Modifier::KeepLow(1)), 6),
+            (postfix_expr(binary_roll_expr(5, 10), Modifier::DropHigh(2)), 30),
+            (postfix_expr(binary_roll_expr(5, 10), Modifier::DropLow(2)), 30),
+            (fudge_roll(numeric_literal(1)), 1),
+            (fudge_roll(numeric_literal(4)), 4),
+            (percentile_roll(numeric_literal(1)), 100),
+            (percentile_roll(numeric_literal(2)), 200),
         ];
 
         tests.iter().for_each(|(input, expected)| {
             assert_eq!(
-                eval(|| 1.0)(input.clone()),
+                eval(|| 1.0)(input.clone()).unwrap(),
                 *expected,
                 "for input `{:#?}`",
                 input
             );
         })
     }
+
+    #[test]
+    fn test_eval_breakdown() {
+        let tests = [
+            (binary_roll_expr(1, 20), 20, 1),
+            (
+                binary_expr(binary_roll_expr(3, 6), numeric_literal(2), Operator::Plus),
+                20,
+                3,
+            ),
+            (numeric_literal(7), 7, 0),
+            (fudge_roll(numeric_literal(4)), 4, 4),
+            (percentile_roll(numeric_literal(2)), 200, 2),
+        ];
+
+        tests
+            .iter()
+            .for_each(|(input, expected_total, expected_rolls)| {
+                let result = eval_breakdown(|| 1.0)(input.clone()).unwrap();
+                assert_eq!(result.total(), *expected_total, "for input `{:#?}`", input);
+                assert_eq!(result.rolls.len(), *expected_rolls, "for input `{:#?}`", input);
+            })
+    }
+
+    #[test]
+    fn test_eval_invalid() {
+        let tests = [
+            postfix_expr(numeric_literal(5), Modifier::Keep(3)),
+            postfix_expr(numeric_literal(2), Modifier::KeepLow(1)),
+            postfix_expr(numeric_literal(3), Modifier::Explode),
+        ];
+
+        tests.iter().for_each(|input| {
+            assert!(eval(|| 1.0)(input.clone()).is_err(), "for input `{:#?}`", input);
+            assert!(
+                eval_breakdown(|| 1.0)(input.clone()).is_err(),
+                "for input `{:#?}`",
+                input
+            );
+        })
+    }
 }
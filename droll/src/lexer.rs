@@ -1,8 +1,11 @@
 use std::{
+    fmt::{Display, Formatter},
     iter::{self, Peekable},
     str::Chars,
 };
 
+use crate::error::Error;
+
 /// Represents the different types of tokens that can be parsed from the input.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Token {
@@ -11,9 +14,36 @@ pub enum Token {
     Minus,
     Asterisk,
     Slash,
+    Caret,
     Die,
     FudgeDie,
     PercentileDie,
+    Keep,
+    KeepLow,
+    DropHigh,
+    DropLow,
+    Explode,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Integer(n) => write!(f, "{}", n),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Asterisk => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Caret => write!(f, "^"),
+            Token::Die => write!(f, "d"),
+            Token::FudgeDie => write!(f, "dF"),
+            Token::PercentileDie => write!(f, "d%"),
+            Token::Keep => write!(f, "k"),
+            Token::KeepLow => write!(f, "kl"),
+            Token::DropHigh => write!(f, "dh"),
+            Token::DropLow => write!(f, "dl"),
+            Token::Explode => write!(f, "!"),
+        }
+    }
 }
 
 /// Performs lexical analysis of the provided string, transforming it into a vector of [`Token`]s.
@@ -24,8 +54,8 @@ pub enum Token {
 /// - A [`Token`] represents a type of dice notation unit parsed from the input string.
 ///
 /// - The function returns a [`Result`] to handle potential errors:
-///   - `Err(String)`: Signifies that unexpected or invalid characters were encountered during
-///     tokenization.
+///   - `Err(Error)`: Signifies that unexpected or invalid characters were encountered during
+///     tokenization, carrying the offending column for reporting.
 ///   - `Ok(Vec<Token>)`: Indicates a successful tokenization process, producing a vector
 ///     containing the identified dice notation tokens.
 ///
@@ -41,12 +71,23 @@ pub enum Token {
 ///
 /// assert_eq!(vec![Token::Integer(1), Token::Die, Token::Integer(20), Token::Plus, Token::Integer(10)], tokens);
 /// ```
-pub fn lex(input: &str) -> Result<Vec<Token>, String> {
+pub fn lex(input: &str) -> Result<Vec<Token>, Error> {
+    Ok(lex_spanned(input)?
+        .into_iter()
+        .map(|(token, _)| token)
+        .collect())
+}
+
+/// Performs the same lexical analysis as [`lex`], but pairs every [`Token`] with the byte offset at
+/// which it begins. The parser relies on these offsets to report positioned diagnostics.
+pub(crate) fn lex_spanned(input: &str) -> Result<Vec<(Token, usize)>, Error> {
     let mut chars = input.chars().peekable();
-    let mut tokens: Vec<Token> = Vec::new();
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let length = input.chars().count();
 
-    while let Some(&char) = chars.to_owned().peek() {
-        tokens.push(parse(char, &mut chars)?);
+    while let Some(&char) = chars.peek() {
+        let offset = length - chars.clone().count();
+        tokens.push((parse(char, &mut chars, offset)?, offset));
     }
 
     Ok(tokens)
@@ -59,25 +100,37 @@ fn parse_integer_token(chars: &mut Peekable<Chars>) -> Result<Token, String> {
         .collect::<String>()
         .parse::<u64>()
         .map(Token::Integer)
-        .map_err(|err| format!("Failed to parse number token: {}", err.to_string()))
+        .map_err(|err| format!("failed to parse number token: {}", err.to_string()))
 }
 
-/// Converts input stream of chars into a die token if the following char is either F, for fudge, %
-/// for percentile or any number of digits for any die.
+/// Converts input stream of chars into a die token. A following `F` selects a Fudge die, `%` a
+/// percentile die and `h`/`l` the drop-high/drop-low modifiers; anything else yields a bare die
+/// token, leaving the operand to the parser so it can report a missing right-hand side positionally.
 fn parse_die_token(chars: &mut Peekable<Chars>) -> Result<Token, String> {
-    let parse = |c| match c {
-        'F' => Ok(Token::FudgeDie),
-        '%' => Ok(Token::PercentileDie),
-        '1'..='9' => Ok(Token::Die),
-        c => Err(format!("Unexpected character: {}", c)),
-    };
-
     chars
         .next_if_eq(&'d')
-        .and(chars.next_if(|&c| c == 'F' || c == '%'))
-        .or(chars.peek().copied())
-        .ok_or("Unexpected end of input stream".to_string())
-        .and_then(parse)
+        .ok_or("unexpected end of input stream".to_string())?;
+
+    Ok(match chars.next_if(|&c| matches!(c, 'F' | '%' | 'h' | 'l')) {
+        Some('F') => Token::FudgeDie,
+        Some('%') => Token::PercentileDie,
+        Some('h') => Token::DropHigh,
+        Some('l') => Token::DropLow,
+        _ => Token::Die,
+    })
+}
+
+/// Converts an input stream of chars into a keep modifier token, distinguishing the keep lowest
+/// modifier (`kl`) from the keep highest modifier (`k`) by the following char.
+fn parse_keep_token(chars: &mut Peekable<Chars>) -> Result<Token, String> {
+    chars
+        .next_if_eq(&'k')
+        .ok_or("unexpected end of input stream".to_string())?;
+
+    Ok(match chars.next_if_eq(&'l') {
+        Some(_) => Token::KeepLow,
+        None => Token::Keep,
+    })
 }
 
 /// Converts a single [`char`] into a [`Token`].
@@ -87,7 +140,9 @@ fn parse_single_token(char: char, chars: &mut Peekable<Chars>) -> Result<Token,
         '-' => Ok(Token::Minus),
         '*' => Ok(Token::Asterisk),
         '/' => Ok(Token::Slash),
-        c => Err(format!("Unexpected character: {}", c)),
+        '^' => Ok(Token::Caret),
+        '!' => Ok(Token::Explode),
+        c => Err(format!("unexpected character: {}", c)),
     }
     .and_then(|t| {
         chars.next();
@@ -95,13 +150,14 @@ fn parse_single_token(char: char, chars: &mut Peekable<Chars>) -> Result<Token,
     })
 }
 
-fn parse(char: char, chars: &mut Peekable<Chars>) -> Result<Token, String> {
+fn parse(char: char, chars: &mut Peekable<Chars>, offset: usize) -> Result<Token, Error> {
     match char {
         '1'..='9' => parse_integer_token(chars),
-        // TODO: needs to parsed as drop modifier too
         'd' => parse_die_token(chars),
+        'k' => parse_keep_token(chars),
         _ => parse_single_token(char, chars),
     }
+    .map_err(|message| Error::new(message, offset))
 }
 
 #[test]
@@ -128,6 +184,15 @@ fn test_lex_valid() {
                 Token::Integer(10),
             ],
         ),
+        (
+            "d6^2",
+            vec![
+                Token::Die,
+                Token::Integer(6),
+                Token::Caret,
+                Token::Integer(2),
+            ],
+        ),
         (
             "2d20",
             vec![Token::Integer(2), Token::Die, Token::Integer(20)],
@@ -157,6 +222,45 @@ fn test_lex_valid() {
             "+-1234567890",
             vec![Token::Plus, Token::Minus, Token::Integer(1234567890)],
         ),
+        (
+            "4d6k3",
+            vec![
+                Token::Integer(4),
+                Token::Die,
+                Token::Integer(6),
+                Token::Keep,
+                Token::Integer(3),
+            ],
+        ),
+        (
+            "4d6kl1",
+            vec![
+                Token::Integer(4),
+                Token::Die,
+                Token::Integer(6),
+                Token::KeepLow,
+                Token::Integer(1),
+            ],
+        ),
+        (
+            "5d10dh2",
+            vec![
+                Token::Integer(5),
+                Token::Die,
+                Token::Integer(10),
+                Token::DropHigh,
+                Token::Integer(2),
+            ],
+        ),
+        (
+            "3d6!",
+            vec![
+                Token::Integer(3),
+                Token::Die,
+                Token::Integer(6),
+                Token::Explode,
+            ],
+        ),
     ];
 
     tests.iter().for_each(|(input, expected)| {
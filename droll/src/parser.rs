@@ -1,9 +1,17 @@
 use std::{iter::Peekable, slice::Iter};
 
-use crate::ast::{binary_expr, numeric_literal, unary_expr, Expr, Operator};
+use crate::ast::{
+    binary_expr, fudge_roll, numeric_literal, percentile_roll, postfix_expr, unary_expr, Expr,
+    Modifier, Operator,
+};
+use crate::error::Error;
 use crate::lexer;
 use crate::lexer::Token;
 
+/// A [`Token`] paired with the byte offset at which it begins, as produced by
+/// [`lexer::lex_spanned`].
+type Spanned = (Token, usize);
+
 /// First parse function performs a lexical analysis of the given input string to transform the
 /// input into readable tokens then a parse tree is generated from the tokens using
 /// operator-precedence parsing.
@@ -39,74 +47,163 @@ use crate::lexer::Token;
 ///
 /// assert_eq!(binary_expr(binary_roll_expr(1, 20), numeric_literal(10), Operator::Plus), parse_tree);
 /// ```
-pub fn parse(input: &str) -> Result<Expr, String> {
-    Ok(parse_expr(&mut lexer::lex(input)?.iter().peekable(), 0))
+pub fn parse(input: &str) -> Result<Expr, Error> {
+    let tokens = lexer::lex_spanned(input)?;
+    parse_expr(&mut tokens.iter().peekable(), 0, input.chars().count())
 }
 
-fn token_to_operator(token: Token) -> Operator {
-    match token {
-        Token::Plus => Operator::Plus,
-        Token::Minus => Operator::Minus,
-        Token::Die => Operator::Die,
-        op => panic!("bad token {:?}", op),
-    }
+fn infix_binding_power(token: Token) -> Option<(Operator, u8, u8)> {
+    Some(match token {
+        Token::Plus => (Operator::Plus, 1, 2),
+        Token::Minus => (Operator::Minus, 1, 2),
+        // Below the die operator so `2d6*10` multiplies the rolled result, not the face count.
+        Token::Asterisk => (Operator::Multiply, 3, 4),
+        Token::Slash => (Operator::Divide, 3, 4),
+        // Below the die operator so `1d4^2` raises the rolled result, not the die's face count.
+        // Right-associative: the left power exceeds the right so `2^3^2` groups as `2^(3^2)`.
+        Token::Caret => (Operator::Power, 6, 5),
+        Token::Die => (Operator::Die, 7, 8),
+        _ => return None,
+    })
 }
 
-fn infix_binding_power(token: Token) -> (u8, u8) {
+fn postfix_binding_power(token: Token) -> Option<(u8, ())> {
     match token {
-        Token::Plus | Token::Minus => (1, 2),
-        Token::Die => (3, 4),
-        token => panic!("bad token {:?}", token),
+        // Below the die operator's right power so a modifier binds to the whole roll (`4d6k3` is
+        // `(4d6)k3`), yet above arithmetic so it still binds tighter than `+`/`-` and `*`/`/`.
+        Token::Keep | Token::KeepLow | Token::DropHigh | Token::DropLow | Token::Explode => {
+            Some((5, ()))
+        }
+        _ => None,
     }
 }
 
-fn prefix_binding_power(token: Token) -> ((), u8) {
-    match token {
-        Token::Plus | Token::Minus => ((), 5),
-        Token::Die => ((), 7),
-        token => panic!("bad token {:?}", token),
+fn prefix_binding_power(token: Token) -> Option<(Operator, u8)> {
+    Some(match token {
+        Token::Plus => (Operator::Plus, 9),
+        Token::Minus => (Operator::Minus, 9),
+        Token::Die => (Operator::Die, 10),
+        _ => return None,
+    })
+}
+
+fn parse_modifier_count(tokens: &mut Peekable<Iter<'_, Spanned>>, eof: usize) -> Result<usize, Error> {
+    match tokens.next() {
+        Some(&(Token::Integer(n), _)) => Ok(n as usize),
+        Some(&(token, offset)) => Err(Error::new(
+            format!("expected a modifier count, found `{}`", token),
+            offset,
+        )),
+        None => Err(Error::new("unexpected end of input, expected a modifier count", eof)),
     }
 }
 
-fn parse_unary_expr(tokens: &mut Peekable<Iter<'_, Token>>, op_token: Token) -> Expr {
-    let ((), r_bp) = prefix_binding_power(op_token);
-    let rhs = parse_expr(tokens, r_bp);
-    unary_expr(rhs, token_to_operator(op_token))
+fn token_to_modifier(
+    token: Token,
+    tokens: &mut Peekable<Iter<'_, Spanned>>,
+    eof: usize,
+) -> Result<Modifier, Error> {
+    Ok(match token {
+        Token::Keep => Modifier::Keep(parse_modifier_count(tokens, eof)?),
+        Token::KeepLow => Modifier::KeepLow(parse_modifier_count(tokens, eof)?),
+        Token::DropHigh => Modifier::DropHigh(parse_modifier_count(tokens, eof)?),
+        Token::DropLow => Modifier::DropLow(parse_modifier_count(tokens, eof)?),
+        Token::Explode => Modifier::Explode,
+        token => unreachable!("`{}` is not a postfix modifier", token),
+    })
+}
+
+fn parse_unary_expr(
+    tokens: &mut Peekable<Iter<'_, Spanned>>,
+    op_token: Token,
+    offset: usize,
+    eof: usize,
+) -> Result<Expr, Error> {
+    let (op, r_bp) = prefix_binding_power(op_token)
+        .ok_or_else(|| Error::new(format!("unexpected token `{}`", op_token), offset))?;
+
+    if tokens.peek().is_none() {
+        return Err(Error::new(
+            format!("unexpected end of input after `{}`", op_token),
+            eof,
+        ));
+    }
+
+    let rhs = parse_expr(tokens, r_bp, eof)?;
+    Ok(unary_expr(rhs, op))
 }
 
-fn parse_expr(tokens: &mut Peekable<Iter<'_, Token>>, min_binding_power: u8) -> Expr {
+fn parse_expr(
+    tokens: &mut Peekable<Iter<'_, Spanned>>,
+    min_binding_power: u8,
+    eof: usize,
+) -> Result<Expr, Error> {
     let mut lhs = match tokens.next() {
-        Some(&Token::Number(n)) => numeric_literal(n),
-        Some(&op_token) => parse_unary_expr(tokens, op_token),
-        token => panic!("bad token {:?}", token),
+        Some(&(Token::Integer(n), _)) => numeric_literal(n as usize),
+        Some(&(Token::FudgeDie, _)) => fudge_roll(numeric_literal(1)),
+        Some(&(Token::PercentileDie, _)) => percentile_roll(numeric_literal(1)),
+        Some(&(op_token, offset)) => parse_unary_expr(tokens, op_token, offset, eof)?,
+        None => return Err(Error::new("unexpected end of input", eof)),
     };
 
     loop {
-        match tokens.peek() {
-            Some(&&token) => {
-                let (l_bp, r_bp) = infix_binding_power(token);
-                if l_bp < min_binding_power {
-                    break;
-                }
+        let (token, offset) = match tokens.peek() {
+            Some(&&spanned) => spanned,
+            None => break,
+        };
+
+        if let Some((l_bp, ())) = postfix_binding_power(token) {
+            if l_bp < min_binding_power {
+                break;
+            }
 
-                tokens.next();
+            tokens.next();
 
-                let rhs = parse_expr(tokens, r_bp);
+            lhs = postfix_expr(lhs, token_to_modifier(token, tokens, eof)?);
 
-                lhs = binary_expr(lhs, rhs, token_to_operator(token))
+            continue;
+        }
+
+        // Fudge and percentile dice bind their amount like the die operator, but carry no
+        // right-hand sides operand.
+        if matches!(token, Token::FudgeDie | Token::PercentileDie) {
+            let (_, l_bp, _) = infix_binding_power(Token::Die).unwrap();
+            if l_bp < min_binding_power {
+                break;
             }
-            None => break,
+
+            tokens.next();
+
+            lhs = match token {
+                Token::FudgeDie => fudge_roll(lhs),
+                _ => percentile_roll(lhs),
+            };
+
+            continue;
+        }
+
+        let (op, l_bp, r_bp) = infix_binding_power(token)
+            .ok_or_else(|| Error::new(format!("unexpected token `{}`", token), offset))?;
+        if l_bp < min_binding_power {
+            break;
         }
+
+        tokens.next();
+
+        let rhs = parse_expr(tokens, r_bp, eof)?;
+
+        lhs = binary_expr(lhs, rhs, op);
     }
 
-    lhs
+    Ok(lhs)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ast::{
-        binary_expr, binary_roll_expr, numeric_literal, unary_expr, unary_roll_expr, Operator,
+        binary_expr, binary_roll_expr, fudge_roll, numeric_literal, percentile_roll, postfix_expr,
+        unary_expr, unary_roll_expr, Modifier, Operator,
     };
 
     #[test]
@@ -159,10 +256,55 @@ mod tests {
                     Operator::Plus,
                 ),
             ),
+            ("4d6k3", postfix_expr(binary_roll_expr(4, 6), Modifier::Keep(3))),
+            (
+                "4d6kl1",
+                postfix_expr(binary_roll_expr(4, 6), Modifier::KeepLow(1)),
+            ),
+            (
+                "5d10dh2",
+                postfix_expr(binary_roll_expr(5, 10), Modifier::DropHigh(2)),
+            ),
+            ("3d6!", postfix_expr(binary_roll_expr(3, 6), Modifier::Explode)),
+            (
+                "2^3",
+                binary_expr(numeric_literal(2), numeric_literal(3), Operator::Power),
+            ),
+            (
+                "2^3^2",
+                binary_expr(
+                    numeric_literal(2),
+                    binary_expr(numeric_literal(3), numeric_literal(2), Operator::Power),
+                    Operator::Power,
+                ),
+            ),
+            (
+                "1d4^2",
+                binary_expr(binary_roll_expr(1, 4), numeric_literal(2), Operator::Power),
+            ),
+            ("dF", fudge_roll(numeric_literal(1))),
+            ("4dF", fudge_roll(numeric_literal(4))),
+            ("d%", percentile_roll(numeric_literal(1))),
+            ("2d%", percentile_roll(numeric_literal(2))),
         ];
 
         tests.iter().for_each(|(input, expected)| {
             assert_eq!(parse(input).unwrap(), *expected, "for input `{:#?}`", input);
         })
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = [
+            ("d", "unexpected end of input after `d` at column 2"),
+            ("1d", "unexpected end of input at column 3"),
+            ("+", "unexpected end of input after `+` at column 2"),
+            ("4d6k", "unexpected end of input, expected a modifier count at column 5"),
+        ];
+
+        tests.iter().for_each(|(input, expected)| {
+            let error = parse(input).unwrap_err();
+            assert_eq!(error.to_string(), *expected, "for input `{:#?}`", input);
+        })
+    }
 }
@@ -1,4 +1,5 @@
 pub mod ast;
+pub mod error;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
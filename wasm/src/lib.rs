@@ -1,4 +1,8 @@
-use droll::{base_prng_engine, interpreter::eval, parser::parse};
+use droll::{
+    base_prng_engine,
+    interpreter::{eval, eval_breakdown},
+    parser::parse,
+};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -6,10 +10,22 @@ use wasm_bindgen::prelude::*;
 /// @example
 /// roll("1d20+10"); // e.g. 27
 pub fn roll(input: &str) -> Result<isize, String> {
-    match parse(input) {
-        Ok(parse_tree) => Ok(eval(base_prng_engine)(parse_tree)),
-        Err(e) => Err(e),
-    }
+    parse(input)
+        .and_then(|parse_tree| eval(base_prng_engine)(parse_tree))
+        .map_err(|e| e.to_string())
+}
+
+#[wasm_bindgen]
+/// Calculates the roll result from the provided dice notation and returns a breakdown object
+/// carrying both the total and the individual dice that produced it.
+/// @example
+/// roll_breakdown("3d6+2"); // { total: 13, rolls: [{ size: 6, face: 4 }, ...] }
+pub fn roll_breakdown(input: &str) -> Result<JsValue, String> {
+    let result = parse(input)
+        .and_then(|parse_tree| eval_breakdown(base_prng_engine)(parse_tree))
+        .map_err(|e| e.to_string())?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]